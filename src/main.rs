@@ -3,7 +3,12 @@ use crossterm::{
     terminal,
 };
 use rand::Rng;
-use std::{collections::HashMap, io};
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+    fs, io,
+};
 use tui::{layout, style, text, widgets};
 
 type TerminalBackend = tui::backend::CrosstermBackend<io::Stdout>;
@@ -17,21 +22,33 @@ fn main() -> Result<(), io::Error> {
     let backend = TerminalBackend::new(stdout);
     let mut terminal = tui::Terminal::new(backend)?;
 
-    let mut game = Game::new();
+    // resume a previous session if there's a save file lying around, start fresh otherwise
+    let mut game = Game::load(Game::SAVE_PATH).unwrap_or_else(Game::new);
 
     loop {
         terminal.draw(|frame| render(&game, frame))?;
 
         if let Event::Key(key) = event::read()? {
             match key.code {
-                // Quit game when pressing q
-                KeyCode::Char('q') => break,
+                // Save and quit game when pressing q
+                KeyCode::Char('q') => {
+                    game.save(Game::SAVE_PATH).ok();
+                    break;
+                }
 
                 // handle both arrows and vi keybindings for now
                 KeyCode::Char('k') | KeyCode::Up => game.move_up(),
                 KeyCode::Char('j') | KeyCode::Down => game.move_down(),
                 KeyCode::Char('h') | KeyCode::Left => game.move_left(),
                 KeyCode::Char('l') | KeyCode::Right => game.move_right(),
+
+                // switch the info panel tab, toggling back to the log if already selected
+                KeyCode::Char('s') => game.toggle_info_view(InfoView::Stat),
+                KeyCode::Char('t') => game.toggle_info_view(InfoView::Todo),
+                KeyCode::Char('e') => game.toggle_info_view(InfoView::Help),
+
+                // use an item from the inventory
+                KeyCode::Char('u') => game.use_item(),
                 _ => {}
             }
         }
@@ -49,7 +66,7 @@ fn render(game: &Game, frame: &mut TerminalFrame) {
 
     // show an info panel with available "views":
     // event logs, character status, quest todos and game help (eg. keybindings)
-    // for now the panel is empty.
+    // for now only the log view renders a body.
 
     // TODO add helpers for more readable styles
     // these could eventually be turned into custom tui-rs widgets
@@ -69,11 +86,18 @@ fn render(game: &Game, frame: &mut TerminalFrame) {
         text::Span::styled("e", underlined),
         text::Span::raw("lp "),
     ]);
-    let block = widgets::Block::default()
+    let info_block = widgets::Block::default()
         .title(panel_titles)
         .borders(widgets::Borders::ALL)
         .title_alignment(layout::Alignment::Center);
-    frame.render_widget(block, info_panel);
+    let info_container = info_block.inner(info_panel);
+    frame.render_widget(info_block, info_panel);
+
+    match game.info_view() {
+        InfoView::Log => render_log(game, frame, info_container),
+        InfoView::Stat => render_stat(game, frame, info_container),
+        InfoView::Todo | InfoView::Help => {}
+    }
 
     // show a menu of additional commands, not associated with a view
     // use an inventory item; buy items or change character class (only at floor zero);
@@ -100,15 +124,15 @@ fn render(game: &Game, frame: &mut TerminalFrame) {
     // The title of the map panel shows basic stats, mostly hardcoded for now
     let map_block = widgets::Block::default()
         .title(format!(
-            " warrior[10][xx--]@{}.{}.{} ",
-            game.floor, game.character_position.x, game.character_position.y
+            " warrior[10][{}]@{}.{}.{} ",
+            hp_bar(game.character_hp, game.character_max_hp, 4),
+            game.floor,
+            game.character_position.x,
+            game.character_position.y
         ))
         .borders(widgets::Borders::ALL);
     let map_container = map_block.inner(map_panel);
-    let map_spans: Vec<_> = map_as_strings(game, map_container.width, map_container.height)
-        .into_iter()
-        .map(text::Spans::from)
-        .collect();
+    let map_spans = map_as_strings(game, map_container.width, map_container.height);
 
     frame.render_widget(
         widgets::Paragraph::new(map_spans).block(map_block),
@@ -116,6 +140,91 @@ fn render(game: &Game, frame: &mut TerminalFrame) {
     );
 }
 
+/// Render the event log, word-wrapped to the panel width and anchored to the bottom so the
+/// most recent entries are always in view.
+fn render_log(game: &Game, frame: &mut TerminalFrame, area: layout::Rect) {
+    let lines: Vec<_> = game
+        .log_entries()
+        .iter()
+        .map(|message| text::Spans::from(message.as_str()))
+        .collect();
+
+    let wrapped_height: u16 = game
+        .log_entries()
+        .iter()
+        .map(|message| wrapped_line_count(message, area.width))
+        .sum();
+    let scroll = wrapped_height.saturating_sub(area.height);
+
+    frame.render_widget(
+        widgets::Paragraph::new(lines)
+            .wrap(widgets::Wrap { trim: true })
+            .scroll((scroll, 0)),
+        area,
+    );
+}
+
+/// Number of lines a message occupies once word-wrapped to the given width, matching how
+/// `Wrap { trim: true }` breaks lines at whitespace.
+fn wrapped_line_count(message: &str, width: u16) -> u16 {
+    if width == 0 {
+        return 1;
+    }
+    let width = width as usize;
+
+    let mut lines = 1u16;
+    let mut current_len = 0usize;
+    for word in message.split_whitespace() {
+        let word_len = word.chars().count();
+        if current_len == 0 {
+            current_len = word_len;
+        } else if current_len + 1 + word_len <= width {
+            current_len += 1 + word_len;
+        } else {
+            lines += 1;
+            current_len = word_len;
+        }
+    }
+    lines
+}
+
+/// Render HP bars for the character and every monster on the current floor.
+fn render_stat(game: &Game, frame: &mut TerminalFrame, area: layout::Rect) {
+    let mut lines = vec![text::Spans::from(format!(
+        "you      {} {}/{}",
+        hp_bar(game.character_hp, game.character_max_hp, 10),
+        game.character_hp,
+        game.character_max_hp
+    ))];
+
+    for monster in game.map().monsters() {
+        lines.push(text::Spans::from(format!(
+            "{:<8} {} {}/{}",
+            monster.name,
+            hp_bar(monster.hp, monster.max_hp, 10),
+            monster.hp,
+            monster.max_hp
+        )));
+    }
+
+    frame.render_widget(
+        widgets::Paragraph::new(lines).wrap(widgets::Wrap { trim: true }),
+        area,
+    );
+}
+
+/// Render an ASCII HP bar (e.g. "[###-------]") `width` characters wide.
+fn hp_bar(hp: i32, max_hp: i32, width: usize) -> String {
+    let filled = if max_hp > 0 {
+        ((hp.max(0) as f64 / max_hp as f64) * width as f64).round() as usize
+    } else {
+        0
+    }
+    .min(width);
+
+    format!("[{}{}]", "#".repeat(filled), "-".repeat(width - filled))
+}
+
 /// Split the available frame size in three blocks:
 ///   a panel to display information (e.g. battle logs)
 ///   a menu of available actions (e.g. quit or reset game)
@@ -136,36 +245,82 @@ fn layout(frame_size: layout::Rect) -> [layout::Rect; 3] {
     [vertical_chunks[0], vertical_chunks[1], horizontal_chunks[1]]
 }
 
-/// Return a vector of strings representing the current map according to the player position
+/// Style applied to a dimmed, revealed-but-not-currently-visible tile.
+const DIMMED: style::Style = style::Style {
+    fg: Some(style::Color::DarkGray),
+    bg: None,
+    add_modifier: style::Modifier::empty(),
+    sub_modifier: style::Modifier::empty(),
+};
+
+/// Style applied to monster glyphs, so they stand out from the terrain.
+const MONSTER_STYLE: style::Style = style::Style {
+    fg: Some(style::Color::Red),
+    bg: None,
+    add_modifier: style::Modifier::empty(),
+    sub_modifier: style::Modifier::empty(),
+};
+
+/// Return the text spans representing the current map according to the player position
 /// and available terminal view size. When a dimension (horizontal or vertical) fits entirely in the view,
 /// the map will be centered in the screen in that direction.
 /// When it doesn't fit, the character will be fixed at the center of the view for that dimension,
 /// and the map will scroll when the character moves.
-fn map_as_strings(game: &Game, view_width: u16, view_height: u16) -> Vec<String> {
+/// Tiles that were never seen render as empty space, tiles seen before but currently out of
+/// sight render dimmed, and tiles within the character's field of view render normally.
+fn map_as_strings(game: &Game, view_width: u16, view_height: u16) -> Vec<text::Spans<'static>> {
     let char_x = game.character_position.x;
     let char_y = game.character_position.y;
 
     // loop through all visible terminal positions, building a span of text for each row in the map
     let mut rows = Vec::new();
     for vy in 0..view_height {
-        let mut row = String::new();
+        // group consecutive same-styled tiles into a single span per row
+        let mut spans = Vec::new();
+        let mut run = String::new();
+        let mut run_style = style::Style::default();
 
         for vx in 0..view_width {
             let mx = to_world_coords(vx, char_x, view_width, game.map().width);
             let my = to_world_coords(vy, char_y, view_height, game.map().height);
 
-            // if there's a tile at this position, push its ascii representation to the text row
-            // otherwise just add an empty space
-            let tile = match (mx, my) {
-                (Some(x), Some(y)) if (x, y) == (char_x, char_y) => Tile::Character,
-                (Some(x), Some(y)) => game.map().tile_at(&Position { x, y }),
-                _ => Tile::Empty,
+            let (symbol, tile_style) = match (mx, my) {
+                (Some(x), Some(y)) if (x, y) == (char_x, char_y) => {
+                    (Tile::Character.to_string(), style::Style::default())
+                }
+                (Some(x), Some(y)) => {
+                    let position = Position { x, y };
+                    if game.map().is_visible(&position) {
+                        if let Some(monster) = game.map().monster_at(&position) {
+                            (monster.glyph.to_string(), MONSTER_STYLE)
+                        } else {
+                            (
+                                game.map().tile_at(&position).to_string(),
+                                style::Style::default(),
+                            )
+                        }
+                    } else if game.map().is_revealed(&position) {
+                        (game.map().tile_at(&position).to_string(), DIMMED)
+                    } else {
+                        (Tile::Empty.to_string(), style::Style::default())
+                    }
+                }
+                _ => (Tile::Empty.to_string(), style::Style::default()),
             };
 
-            row.push_str(&tile.to_string());
+            if tile_style != run_style && !run.is_empty() {
+                spans.push(text::Span::styled(run.clone(), run_style));
+                run.clear();
+            }
+            run_style = tile_style;
+            run.push_str(&symbol);
+        }
+
+        if !run.is_empty() {
+            spans.push(text::Span::styled(run, run_style));
         }
 
-        rows.push(row);
+        rows.push(text::Spans::from(spans));
     }
 
     rows
@@ -190,24 +345,46 @@ fn to_world_coords(view_x: u16, player_x: u16, view_width: u16, map_width: u16)
     }
 }
 
+#[derive(Serialize, Deserialize)]
 struct Game {
     pub floor: usize,
     // this may eventually need to distinguish between tilemap and itemmap, maybe moving char position back to the map
     maps: Vec<Map>,
     pub character_position: Position,
+    pub character_hp: i32,
+    pub character_max_hp: i32,
+    inventory: Vec<ItemKind>,
+    event_log: Log,
+    info_view: InfoView,
 }
 
 impl Game {
+    // how far, in tiles, the character can see around itself
+    const TORCH_RADIUS: u16 = 8;
+
+    // default location of the persisted game state
+    pub const SAVE_PATH: &'static str = "savegame.ron";
+
+    const STARTING_HP: i32 = 20;
+    const PLAYER_ATTACK_DAMAGE: i32 = 4;
+    const MONSTER_ATTACK_DAMAGE: i32 = 2;
+
     /// Start a game with an initial map for the ground floor.
     /// Additional maps will be added as the player moves down.
     pub fn new() -> Self {
-        let first_map = Map::new(0);
-        let character_position = first_map.random_unocuppied_position();
-        Self {
+        let (first_map, character_position) = Map::new(0);
+        let mut game = Self {
             floor: 0,
             character_position,
+            character_hp: Self::STARTING_HP,
+            character_max_hp: Self::STARTING_HP,
+            inventory: Vec::new(),
             maps: vec![first_map],
-        }
+            event_log: Log::new(),
+            info_view: InfoView::Log,
+        };
+        game.update_fov();
+        game
     }
 
     /// Return the map the player is currently at.
@@ -251,6 +428,14 @@ impl Game {
     /// (e.g. if there isn't a wall there). If the destination is an up or down ladder,
     /// move the character to the corresponding floor.
     fn move_to(&mut self, dest_position: Position) {
+        // bumping into a monster attacks it instead of moving onto its tile
+        if let Some(message) = self.attack_monster_at(&dest_position) {
+            self.log(message);
+            self.update_fov();
+            self.run_monsters();
+            return;
+        }
+
         match self.map().tile_at(&dest_position) {
             // When stepping on a down ladder, move to the next floor at the position of the up ladder.
             // The map is created if the floor hasn't been visited before
@@ -258,13 +443,15 @@ impl Game {
                 self.floor += 1;
 
                 if self.floor == self.maps.len() {
-                    self.maps.push(Map::new(self.floor));
+                    self.maps.push(Map::new(self.floor).0);
                 }
 
                 self.character_position = self
                     .map()
                     .find_tile(Tile::LadderUp)
                     .expect("all non zero floors have a ladder up");
+
+                self.log(format!("You descend to floor {}.", self.floor));
             }
 
             // When stepping on a down ladder, move to the previous floor at the position of the down ladder.
@@ -275,23 +462,219 @@ impl Game {
                     .map()
                     .find_tile(Tile::LadderDown)
                     .expect("all floors have a ladder down");
+
+                self.log("You find stairs up.");
             }
 
             // Do nothing if attempting to move into a wall.
-            Tile::Wall => {}
+            Tile::Wall => {
+                self.log("You bump into a wall.");
+            }
+
+            // Step onto the item, picking it up and leaving plain ground behind.
+            Tile::Item(kind) => {
+                self.maps[self.floor]
+                    .tiles
+                    .insert(dest_position.clone(), Tile::Ground);
+                self.character_position = dest_position;
+                self.log(format!("You pick up {}.", kind));
+                self.inventory.push(kind);
+            }
 
             // Otherwise update the current position
             _ => {
                 self.character_position = dest_position;
             }
         }
+
+        self.update_fov();
+        self.run_monsters();
+    }
+
+    /// Attack the monster at `position`, if there's a living one there. Returns a log message
+    /// describing the result, or `None` if there was nothing to attack.
+    fn attack_monster_at(&mut self, position: &Position) -> Option<String> {
+        let map = &mut self.maps[self.floor];
+        let index = map.monsters.iter().position(|m| &m.position == position)?;
+
+        map.monsters[index].hp -= Self::PLAYER_ATTACK_DAMAGE;
+
+        if map.monsters[index].hp <= 0 {
+            let monster = map.monsters.remove(index);
+            Some(format!("You defeat the {}.", monster.name))
+        } else {
+            Some(format!(
+                "You hit the {} for {} damage.",
+                map.monsters[index].name,
+                Self::PLAYER_ATTACK_DAMAGE
+            ))
+        }
+    }
+
+    /// Move or attack with every monster currently in the character's field of view.
+    fn run_monsters(&mut self) {
+        let character_position = self.character_position.clone();
+        let monster_count = self.maps[self.floor].monsters.len();
+
+        for i in 0..monster_count {
+            let monster_position = self.maps[self.floor].monsters[i].position.clone();
+
+            if !self.maps[self.floor].is_visible(&monster_position) {
+                continue;
+            }
+
+            if Map::manhattan_distance(&monster_position, &character_position) <= 1 {
+                let name = self.maps[self.floor].monsters[i].name.clone();
+                self.character_hp -= Self::MONSTER_ATTACK_DAMAGE;
+                self.log(format!(
+                    "The {} hits you for {} damage.",
+                    name,
+                    Self::MONSTER_ATTACK_DAMAGE
+                ));
+                continue;
+            }
+
+            let next_step = self.maps[self.floor]
+                .find_path(&monster_position, &character_position)
+                .and_then(|path| path.into_iter().next());
+
+            if let Some(next_step) = next_step {
+                self.maps[self.floor].monsters[i].position = next_step;
+            }
+        }
+    }
+
+    /// Use the first usable item the character is carrying: a sledge digs through an
+    /// adjacent wall, a ladder piece drops a new ladder down at the character's feet.
+    pub fn use_item(&mut self) {
+        if let Some(index) = self
+            .inventory
+            .iter()
+            .position(|item| *item == ItemKind::Sledge)
+        {
+            if self.dig_adjacent_wall() {
+                self.inventory.remove(index);
+            }
+        } else if let Some(index) = self
+            .inventory
+            .iter()
+            .position(|item| *item == ItemKind::LadderPiece)
+        {
+            self.place_ladder_down();
+            self.inventory.remove(index);
+        } else {
+            self.log("You have nothing to use.");
+        }
+    }
+
+    /// Dig the first adjacent wall tile into ground. Returns whether a wall was found.
+    fn dig_adjacent_wall(&mut self) -> bool {
+        let position = self.character_position.clone();
+        let candidates = [
+            position
+                .y
+                .checked_sub(1)
+                .map(|y| Position { x: position.x, y }),
+            Some(Position {
+                x: position.x,
+                y: position.y + 1,
+            }),
+            position
+                .x
+                .checked_sub(1)
+                .map(|x| Position { x, y: position.y }),
+            Some(Position {
+                x: position.x + 1,
+                y: position.y,
+            }),
+        ];
+
+        for candidate in candidates.into_iter().flatten() {
+            // never dig out the map's outer border: that's the only thing keeping
+            // movement and digging from running off the edge of the grid
+            if self.map().is_border(&candidate) {
+                continue;
+            }
+
+            if self.map().tile_at(&candidate) == Tile::Wall {
+                self.maps[self.floor].tiles.insert(candidate, Tile::Ground);
+                self.log("You dig through the wall with your sledge.");
+                return true;
+            }
+        }
+
+        self.log("There's no wall to dig here.");
+        false
+    }
+
+    /// Drop a new down ladder at the character's current position, replacing the
+    /// floor's existing one so there's never more than one to climb down from.
+    fn place_ladder_down(&mut self) {
+        if let Some(old) = self.maps[self.floor].find_tile(Tile::LadderDown) {
+            self.maps[self.floor].tiles.insert(old, Tile::Ground);
+        }
+
+        let position = self.character_position.clone();
+        self.maps[self.floor]
+            .tiles
+            .insert(position, Tile::LadderDown);
+        self.log("You place a ladder piece, opening a shortcut down.");
+    }
+
+    /// Push a message to the event log, dropping the oldest entry once it's at capacity.
+    pub fn log(&mut self, message: impl Into<String>) {
+        self.event_log.push(message);
+    }
+
+    /// The event log entries, oldest first.
+    pub fn log_entries(&self) -> &VecDeque<String> {
+        &self.event_log.messages
+    }
+
+    /// The info panel view currently selected.
+    pub fn info_view(&self) -> InfoView {
+        self.info_view
+    }
+
+    /// Switch to the given info view, or back to the log if it's already selected.
+    pub fn toggle_info_view(&mut self, view: InfoView) {
+        self.info_view = if self.info_view == view {
+            InfoView::Log
+        } else {
+            view
+        };
+    }
+
+    /// Recompute which tiles are lit and revealed around the character's current position.
+    fn update_fov(&mut self) {
+        let origin = self.character_position.clone();
+        self.maps[self.floor].recompute_fov(origin, Self::TORCH_RADIUS);
+    }
+
+    /// Write the full game state (floor, character position and every generated map) to `path`.
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        let serialized =
+            ron::to_string(self).expect("in-memory game state should always serialize");
+        fs::write(path, serialized)
+    }
+
+    /// Load a previously saved game state from `path`, if it exists and is valid.
+    pub fn load(path: &str) -> Option<Self> {
+        let serialized = fs::read_to_string(path).ok()?;
+        ron::from_str(&serialized).ok()
     }
 }
 
+#[derive(Serialize, Deserialize)]
 struct Map {
     pub width: u16,
     pub height: u16,
     tiles: HashMap<Position, Tile>,
+    // tiles the character has ever seen
+    revealed: HashSet<Position>,
+    // tiles lit by the character's torch this turn
+    visible: HashSet<Position>,
+    monsters: Vec<Entity>,
 }
 
 impl Map {
@@ -300,8 +683,23 @@ impl Map {
     const MIN_HEIGHT: u16 = 10;
     const MAX_HEIGHT: u16 = 50;
 
-    /// Create a map for the first floor, with randomly placed character and down ladder.
-    pub fn new(floor: usize) -> Self {
+    // dungeon generation tuning: how many rooms to try to fit, and their size range
+    const ROOM_ATTEMPTS: usize = 30;
+    const MIN_ROOM_SIZE: u16 = 6;
+    const MAX_ROOM_SIZE: u16 = 10;
+
+    // at most this many monsters get spawned in any one room
+    const MAX_MONSTERS_PER_ROOM: usize = 3;
+
+    // how many gold piles get scattered across the floor
+    const GOLD_PILES: usize = 3;
+
+    /// Create a map for the given floor: a dungeon of several non-overlapping rooms
+    /// joined by corridors, with ladders placed on carved-out floor tiles and
+    /// monsters scattered across the rooms. Also returns the entrance position: the
+    /// up ladder for floors above zero, or the first room's center for floor zero,
+    /// which is where the character is about to be placed.
+    pub fn new(floor: usize) -> (Self, Position) {
         let mut rng = rand::thread_rng();
         let width = rng.gen_range(Self::MIN_WIDTH..=Self::MAX_WIDTH);
         let height = rng.gen_range(Self::MIN_HEIGHT..=Self::MAX_HEIGHT);
@@ -310,28 +708,221 @@ impl Map {
             width,
             height,
             tiles: HashMap::new(),
+            revealed: HashSet::new(),
+            visible: HashSet::new(),
+            monsters: Vec::new(),
         };
 
-        // For now generate rectangular maps: a single room covering the whole map with walls
-        // along the borders
+        // start from a solid block of walls and carve rooms and corridors out of it
         for x in 0..map.width {
             for y in 0..map.height {
-                let tile = if x == 0 || x == map.width - 1 || y == 0 || y == map.height - 1 {
-                    Tile::Wall
-                } else {
-                    Tile::Ground
-                };
-                map.tiles.insert(Position { x, y }, tile);
+                map.tiles.insert(Position { x, y }, Tile::Wall);
+            }
+        }
+
+        // on floor zero the first room's center doubles as the character's starting
+        // position, so no monster gets to spawn on top of it
+        let mut floor_zero_entrance = None;
+
+        let mut rooms: Vec<Room> = Vec::new();
+        for _ in 0..Self::ROOM_ATTEMPTS {
+            let room = Room::random(&mut rng, map.width, map.height);
+
+            if rooms.iter().any(|other| room.overlaps(other)) {
+                continue;
+            }
+
+            map.carve_room(&room);
+
+            if floor == 0 && rooms.is_empty() {
+                floor_zero_entrance = Some(room.center());
+            }
+            map.spawn_monsters(&room, floor_zero_entrance.as_ref(), &mut rng);
+
+            if let Some(previous) = rooms.last() {
+                map.carve_tunnel(previous.center(), room.center(), &mut rng);
             }
+
+            rooms.push(room);
         }
 
-        map.tiles
-            .insert(map.random_unocuppied_position(), Tile::LadderDown);
-        if floor > 0 {
+        // place the down ladder as far as possible from the entrance, so descending
+        // requires actually exploring the floor instead of an early lucky random pick
+        let entrance = if floor > 0 {
+            let up_position = map.random_unocuppied_position();
+            map.tiles.insert(up_position.clone(), Tile::LadderUp);
+            up_position
+        } else {
+            floor_zero_entrance.unwrap_or_else(|| map.random_unocuppied_position())
+        };
+        map.place_farthest_ladder_down(&entrance);
+
+        map.tiles.insert(
+            map.random_unocuppied_position(),
+            Tile::Item(ItemKind::Sledge),
+        );
+        map.tiles.insert(
+            map.random_unocuppied_position(),
+            Tile::Item(ItemKind::LadderPiece),
+        );
+        for _ in 0..Self::GOLD_PILES {
             map.tiles
-                .insert(map.random_unocuppied_position(), Tile::LadderUp);
+                .insert(map.random_unocuppied_position(), Tile::Item(ItemKind::Gold));
+        }
+
+        (map, entrance)
+    }
+
+    /// Carve a room's interior to ground.
+    fn carve_room(&mut self, room: &Room) {
+        for x in room.x..room.x + room.width {
+            for y in room.y..room.y + room.height {
+                self.tiles.insert(Position { x, y }, Tile::Ground);
+            }
+        }
+    }
+
+    /// Carve an L-shaped corridor of ground tiles between two points, picking the
+    /// elbow direction (horizontal-then-vertical or vertical-then-horizontal) at random.
+    fn carve_tunnel(&mut self, from: Position, to: Position, rng: &mut impl Rng) {
+        if rng.gen_bool(0.5) {
+            self.carve_horizontal_tunnel(from.y, from.x, to.x);
+            self.carve_vertical_tunnel(to.x, from.y, to.y);
+        } else {
+            self.carve_vertical_tunnel(from.x, from.y, to.y);
+            self.carve_horizontal_tunnel(to.y, from.x, to.x);
         }
-        map
+    }
+
+    fn carve_horizontal_tunnel(&mut self, y: u16, x1: u16, x2: u16) {
+        for x in x1.min(x2)..=x1.max(x2) {
+            self.tiles.insert(Position { x, y }, Tile::Ground);
+        }
+    }
+
+    fn carve_vertical_tunnel(&mut self, x: u16, y1: u16, y2: u16) {
+        for y in y1.min(y2)..=y1.max(y2) {
+            self.tiles.insert(Position { x, y }, Tile::Ground);
+        }
+    }
+
+    /// Scatter up to `MAX_MONSTERS_PER_ROOM` monsters across a freshly carved room,
+    /// never stacking two monsters on the same tile or one on `avoid` (the
+    /// character's starting position, when this is the entrance room).
+    fn spawn_monsters(&mut self, room: &Room, avoid: Option<&Position>, rng: &mut impl Rng) {
+        let count = rng.gen_range(0..=Self::MAX_MONSTERS_PER_ROOM);
+
+        for _ in 0..count {
+            let position = loop {
+                let candidate = Position {
+                    x: rng.gen_range(room.x..room.x + room.width),
+                    y: rng.gen_range(room.y..room.y + room.height),
+                };
+                if self.monster_at(&candidate).is_none() && Some(&candidate) != avoid {
+                    break candidate;
+                }
+            };
+            self.monsters.push(Entity::goblin(position));
+        }
+    }
+
+    /// The monsters currently alive on this floor.
+    pub fn monsters(&self) -> &[Entity] {
+        &self.monsters
+    }
+
+    /// Return the monster standing at the given position, if any.
+    pub fn monster_at(&self, position: &Position) -> Option<&Entity> {
+        self.monsters
+            .iter()
+            .find(|monster| &monster.position == position)
+    }
+
+    /// Find the shortest path between two walkable tiles using A* with a Manhattan distance
+    /// heuristic over 4-directional neighbors. The returned path excludes `from` and includes
+    /// `to`; it is `None` when no path exists.
+    fn find_path(&self, from: &Position, to: &Position) -> Option<Vec<Position>> {
+        let mut open_set = BinaryHeap::new();
+        open_set.push(Reverse((Self::manhattan_distance(from, to), from.clone())));
+
+        let mut came_from: HashMap<Position, Position> = HashMap::new();
+        let mut best_cost: HashMap<Position, i32> = HashMap::new();
+        best_cost.insert(from.clone(), 0);
+
+        while let Some(Reverse((_, current))) = open_set.pop() {
+            if current == *to {
+                return Some(Self::reconstruct_path(&came_from, current));
+            }
+
+            let current_cost = best_cost[&current];
+
+            for neighbor in self.walkable_neighbors(&current) {
+                let cost = current_cost + 1;
+                if cost < *best_cost.get(&neighbor).unwrap_or(&i32::MAX) {
+                    came_from.insert(neighbor.clone(), current.clone());
+                    best_cost.insert(neighbor.clone(), cost);
+                    let priority = cost + Self::manhattan_distance(&neighbor, to);
+                    open_set.push(Reverse((priority, neighbor)));
+                }
+            }
+        }
+
+        None
+    }
+
+    fn reconstruct_path(came_from: &HashMap<Position, Position>, goal: Position) -> Vec<Position> {
+        let mut path = vec![goal.clone()];
+        let mut current = goal;
+
+        while let Some(previous) = came_from.get(&current) {
+            path.push(previous.clone());
+            current = previous.clone();
+        }
+
+        // the starting tile shouldn't be part of the path to walk
+        path.pop();
+        path.reverse();
+        path
+    }
+
+    /// The walkable (non-wall, in-bounds) 4-directional neighbors of a position.
+    fn walkable_neighbors(&self, position: &Position) -> Vec<Position> {
+        let mut neighbors = Vec::new();
+
+        if let Some(x) = position.x.checked_sub(1) {
+            neighbors.push(Position { x, y: position.y });
+        }
+        if position.x + 1 < self.width {
+            neighbors.push(Position {
+                x: position.x + 1,
+                y: position.y,
+            });
+        }
+        if let Some(y) = position.y.checked_sub(1) {
+            neighbors.push(Position { x: position.x, y });
+        }
+        if position.y + 1 < self.height {
+            neighbors.push(Position {
+                x: position.x,
+                y: position.y + 1,
+            });
+        }
+
+        neighbors
+            .into_iter()
+            .filter(|position| self.is_walkable(position))
+            .collect()
+    }
+
+    fn is_walkable(&self, position: &Position) -> bool {
+        matches!(
+            self.tile_at(position),
+            Tile::Ground | Tile::LadderUp | Tile::LadderDown
+        )
+    }
+
+    fn manhattan_distance(a: &Position, b: &Position) -> i32 {
+        (i32::from(a.x) - i32::from(b.x)).abs() + (i32::from(a.y) - i32::from(b.y)).abs()
     }
 
     /// Return the position of the first tile of the given type found in the map or None if not found.
@@ -348,8 +939,141 @@ impl Map {
         self.tiles.get(position).cloned().unwrap_or(Tile::Empty)
     }
 
-    /// Return a random position within the map that can be used to place an object.
-    /// For now, this means that there's no tile or a ground type tile in it.
+    /// Whether the given position sits on the map's permanent outer border.
+    pub fn is_border(&self, position: &Position) -> bool {
+        position.x == 0
+            || position.y == 0
+            || position.x == self.width - 1
+            || position.y == self.height - 1
+    }
+
+    /// Whether the given position is currently lit by the character's torch.
+    pub fn is_visible(&self, position: &Position) -> bool {
+        self.visible.contains(position)
+    }
+
+    /// Whether the given position has been seen by the character at some point.
+    pub fn is_revealed(&self, position: &Position) -> bool {
+        self.revealed.contains(position)
+    }
+
+    /// Recompute the set of tiles visible from `origin` within `radius` tiles, using a
+    /// symmetric raycast: a tile is lit if nothing blocks the straight line to it, and a
+    /// wall is lit too if it borders a lit floor tile, so room outlines read clearly.
+    /// Every tile that becomes visible is also marked as revealed, permanently.
+    fn recompute_fov(&mut self, origin: Position, radius: u16) {
+        self.visible.clear();
+
+        let min_x = origin.x.saturating_sub(radius);
+        let max_x = (origin.x + radius).min(self.width.saturating_sub(1));
+        let min_y = origin.y.saturating_sub(radius);
+        let max_y = (origin.y + radius).min(self.height.saturating_sub(1));
+        let radius_squared = i32::from(radius) * i32::from(radius);
+
+        for x in min_x..=max_x {
+            for y in min_y..=max_y {
+                let target = Position { x, y };
+                if Self::squared_distance(&origin, &target) <= radius_squared
+                    && self.has_line_of_sight(&origin, &target)
+                {
+                    self.visible.insert(target);
+                }
+            }
+        }
+
+        let lit_walls: Vec<Position> = self
+            .tiles
+            .iter()
+            .filter(|(position, tile)| {
+                **tile == Tile::Wall && self.is_adjacent_to_visible(position)
+            })
+            .map(|(position, _)| position.clone())
+            .collect();
+        self.visible.extend(lit_walls);
+
+        self.revealed.extend(self.visible.iter().cloned());
+    }
+
+    fn squared_distance(a: &Position, b: &Position) -> i32 {
+        let dx = i32::from(a.x) - i32::from(b.x);
+        let dy = i32::from(a.y) - i32::from(b.y);
+        dx * dx + dy * dy
+    }
+
+    /// Place a down ladder on the walkable tile farthest (by squared Euclidean distance)
+    /// from `seed`, so the player has to cross the floor to find it.
+    fn place_farthest_ladder_down(&mut self, seed: &Position) {
+        let position = self.farthest_walkable_position(seed);
+        self.tiles.insert(position, Tile::LadderDown);
+    }
+
+    fn farthest_walkable_position(&self, seed: &Position) -> Position {
+        self.tiles
+            .iter()
+            .filter(|(position, tile)| {
+                **tile == Tile::Ground && self.monster_at(position).is_none()
+            })
+            .map(|(position, _)| position.clone())
+            .max_by_key(|position| Self::squared_distance(seed, position))
+            .unwrap_or_else(|| self.random_unocuppied_position())
+    }
+
+    /// Walk a Bresenham line from `from` to `to`, stopping if a wall blocks the way before
+    /// the target. The target tile itself is always considered visible, even if it's a wall,
+    /// so the player can see the walls around them.
+    fn has_line_of_sight(&self, from: &Position, to: &Position) -> bool {
+        let (x0, y0) = (i32::from(from.x), i32::from(from.y));
+        let (x1, y1) = (i32::from(to.x), i32::from(to.y));
+        let (mut x, mut y) = (x0, y0);
+
+        let dx = (x1 - x0).abs();
+        let dy = -(y1 - y0).abs();
+        let sx = if x0 < x1 { 1 } else { -1 };
+        let sy = if y0 < y1 { 1 } else { -1 };
+        let mut err = dx + dy;
+
+        loop {
+            if (x, y) == (x1, y1) {
+                return true;
+            }
+            if (x, y) != (x0, y0)
+                && self.tile_at(&Position {
+                    x: x as u16,
+                    y: y as u16,
+                }) == Tile::Wall
+            {
+                return false;
+            }
+
+            let doubled_err = 2 * err;
+            if doubled_err >= dy {
+                err += dy;
+                x += sx;
+            }
+            if doubled_err <= dx {
+                err += dx;
+                y += sy;
+            }
+        }
+    }
+
+    /// Whether any of the four orthogonal neighbors of `position` is currently visible.
+    fn is_adjacent_to_visible(&self, position: &Position) -> bool {
+        let neighbors = [
+            (position.x.checked_sub(1), Some(position.y)),
+            (Some(position.x + 1), Some(position.y)),
+            (Some(position.x), position.y.checked_sub(1)),
+            (Some(position.x), Some(position.y + 1)),
+        ];
+
+        neighbors.into_iter().any(|neighbor| match neighbor {
+            (Some(x), Some(y)) => self.visible.contains(&Position { x, y }),
+            _ => false,
+        })
+    }
+
+    /// Return a random position within the map that can be used to place an object:
+    /// a ground tile with no item, ladder, or monster already on it.
     pub fn random_unocuppied_position(&self) -> Position {
         let mut rng = rand::thread_rng();
 
@@ -358,17 +1082,124 @@ impl Map {
                 x: rng.gen_range(0..self.width),
                 y: rng.gen_range(0..self.height),
             };
-            let tile = self.tiles.get(&pos);
-
-            // FIXME floor is special case, will need a more official way to tell if the position is unoccupied
-            if tile.is_none() || *tile.unwrap() == Tile::Ground {
+            if !self.is_occupied(&pos) {
                 return pos;
             }
         }
     }
+
+    /// Whether the given position already has a monster on it, or a tile other than
+    /// plain ground (a wall, an item, a ladder, ...).
+    fn is_occupied(&self, position: &Position) -> bool {
+        self.monster_at(position).is_some()
+            || !matches!(self.tiles.get(position), None | Some(Tile::Ground))
+    }
+}
+
+/// A rectangular room considered during dungeon generation.
+struct Room {
+    x: u16,
+    y: u16,
+    width: u16,
+    height: u16,
+}
+
+impl Room {
+    /// Build a random room that fits within a map of the given size, leaving at least
+    /// one tile of margin to the map border.
+    fn random(rng: &mut impl Rng, map_width: u16, map_height: u16) -> Self {
+        // clamp the room size so it's always possible to fit it with margin to spare,
+        // even on the smallest maps this generator can produce
+        let max_width = Map::MAX_ROOM_SIZE.min(map_width - 3);
+        let max_height = Map::MAX_ROOM_SIZE.min(map_height - 3);
+        let width = rng.gen_range(Map::MIN_ROOM_SIZE..=max_width);
+        let height = rng.gen_range(Map::MIN_ROOM_SIZE..=max_height);
+
+        let x = rng.gen_range(1..=map_width - 1 - width);
+        let y = rng.gen_range(1..=map_height - 1 - height);
+
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Return the room's center tile, used as the endpoint of corridors.
+    fn center(&self) -> Position {
+        Position {
+            x: self.x + self.width / 2,
+            y: self.y + self.height / 2,
+        }
+    }
+
+    /// Whether this room's bounding rectangle overlaps another's.
+    fn overlaps(&self, other: &Room) -> bool {
+        self.x <= other.x + other.width
+            && other.x <= self.x + self.width
+            && self.y <= other.y + other.height
+            && other.y <= self.y + self.height
+    }
+}
+
+/// Bounded history of event messages ("you bump into a wall", etc), shown in the log info view.
+#[derive(Serialize, Deserialize)]
+struct Log {
+    messages: VecDeque<String>,
+}
+
+impl Log {
+    // how many entries to keep before dropping the oldest
+    const CAPACITY: usize = 100;
+
+    fn new() -> Self {
+        Self {
+            messages: VecDeque::new(),
+        }
+    }
+
+    fn push(&mut self, message: impl Into<String>) {
+        if self.messages.len() == Self::CAPACITY {
+            self.messages.pop_front();
+        }
+        self.messages.push_back(message.into());
+    }
+}
+
+/// Which "view" of the info panel is currently selected (see the panel's tabs).
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+enum InfoView {
+    Log,
+    Stat,
+    Todo,
+    Help,
+}
+
+/// A monster placed on a map, chasing and fighting the character.
+#[derive(Clone, Serialize, Deserialize)]
+struct Entity {
+    pub position: Position,
+    pub hp: i32,
+    pub max_hp: i32,
+    pub glyph: char,
+    pub name: String,
+}
+
+impl Entity {
+    /// Build the only kind of monster the game spawns for now.
+    fn goblin(position: Position) -> Self {
+        Self {
+            position,
+            hp: 8,
+            max_hp: 8,
+            glyph: 'g',
+            name: "goblin".to_string(),
+        }
+    }
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 enum Tile {
     Wall,
     Ground,
@@ -376,6 +1207,7 @@ enum Tile {
     LadderUp,
     LadderDown,
     Empty,
+    Item(ItemKind),
 }
 
 impl std::fmt::Display for Tile {
@@ -387,12 +1219,45 @@ impl std::fmt::Display for Tile {
             Tile::LadderUp => '↑',
             Tile::LadderDown => '↓',
             Tile::Empty => ' ',
+            Tile::Item(kind) => kind.glyph(),
         };
         write!(f, "{}", char)
     }
 }
 
-#[derive(Eq, Hash, PartialEq, Clone)]
+/// Kinds of item the character can pick up and `u`se.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+enum ItemKind {
+    // digs through an adjacent wall when used
+    Sledge,
+    // places a new ladder down at the character's position when used
+    LadderPiece,
+    // has no use yet, just something to collect
+    Gold,
+}
+
+impl ItemKind {
+    fn glyph(&self) -> char {
+        match self {
+            ItemKind::Sledge => '/',
+            ItemKind::LadderPiece => '=',
+            ItemKind::Gold => '$',
+        }
+    }
+}
+
+impl std::fmt::Display for ItemKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ItemKind::Sledge => "a sledge",
+            ItemKind::LadderPiece => "a ladder piece",
+            ItemKind::Gold => "some gold",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Eq, Hash, PartialEq, Ord, PartialOrd, Clone, Serialize, Deserialize)]
 struct Position {
     pub x: u16,
     pub y: u16,